@@ -15,7 +15,9 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 
-use sp_core::{Pair, Public, sr25519, H160, U256};
+use sp_core::{crypto::UncheckedInto, ecdsa, Pair, Public, sr25519, H160, H256, U256};
+use sha3::{Digest, Keccak256};
+use hex_literal::hex;
 use moonbeam_runtime::{
     AccountId, AuraConfig, BalancesConfig, EVMConfig, EthereumConfig, GenesisConfig, GrandpaConfig,
     CouncilConfig, SudoConfig, SystemConfig, WASM_BINARY, Signature
@@ -23,12 +25,18 @@ use moonbeam_runtime::{
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
-use sc_service::ChainType;
+use sc_service::{config::MultiaddrWithPeerId, ChainType, Properties};
+use sc_telemetry::TelemetryEndpoints;
+use serde::Deserialize;
 use std::collections::BTreeMap;
+use std::path::Path;
 use std::str::FromStr;
 
 // The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+
+/// The protocol ID advertised on the libp2p network.
+const PROTOCOL_ID: &str = "moonbeam";
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
@@ -57,99 +65,386 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
         )
 }
 
+/// Derive the Ethereum-style address for a dev seed, i.e. the last 20 bytes of the
+/// Keccak-256 hash of the seed's uncompressed secp256k1 public key. This lets dev
+/// accounts be funded on both the Substrate and EVM sides from the same seed.
+pub fn evm_account_from_seed(seed: &str) -> H160 {
+        let pair = ecdsa::Pair::from_string(&format!("//{}", seed), None)
+                .expect("static values are valid; qed");
+        let uncompressed = libsecp256k1::PublicKey::parse_compressed(&pair.public().0)
+                .expect("ecdsa pair produces a valid compressed public key; qed")
+                .serialize();
+        // Skip the leading 0x04 tag byte of the uncompressed encoding.
+        H160::from_slice(&Keccak256::digest(&uncompressed[1..])[12..])
+}
+
+/// Generate the matching Substrate `AccountId` and EVM `H160` address for a dev seed.
+fn endowed_account_from_seed(seed: &str) -> (AccountId, H160) {
+        (
+                get_account_id_from_seed::<sr25519::Public>(seed),
+                evm_account_from_seed(seed),
+        )
+}
+
+/// A single entry of a Geth-style `alloc` genesis JSON object.
+#[derive(Deserialize)]
+struct AllocAccountJson {
+        #[serde(default)]
+        balance: String,
+        #[serde(default)]
+        nonce: Option<String>,
+        #[serde(default)]
+        code: Option<String>,
+        #[serde(default)]
+        storage: Option<BTreeMap<String, String>>,
+}
+
+/// Parse a decimal or `0x`-prefixed hex string into a `U256`.
+fn parse_u256(value: &str) -> Result<U256, String> {
+        match value.strip_prefix("0x") {
+                Some(hex) => U256::from_str(hex).map_err(|e| format!("invalid hex value `{}`: {}", value, e)),
+                None => U256::from_dec_str(value).map_err(|e| format!("invalid decimal value `{}`: {}", value, e)),
+        }
+}
+
+/// Parse a `0x`-prefixed hex string into an `H256`.
+fn parse_h256(value: &str) -> Result<H256, String> {
+        let hex = value.strip_prefix("0x").unwrap_or(value);
+        H256::from_str(hex).map_err(|e| format!("invalid hex value `{}`: {}", value, e))
+}
+
+/// The top-level shape of a standard Ethereum `genesis.json`. We only care about the
+/// `alloc` map; the other top-level keys (`config`, `difficulty`, `gasLimit`, ...) are
+/// ignored so a real geth genesis file can be pointed at directly.
+#[derive(Deserialize)]
+struct GenesisJson {
+        alloc: BTreeMap<String, AllocAccountJson>,
+}
+
+/// Load a standard Ethereum `genesis.json`'s `alloc` map into the EVM pallet's genesis
+/// account map.
+fn evm_accounts_from_alloc_file(path: &Path) -> Result<BTreeMap<H160, evm::GenesisAccount>, String> {
+        let contents = std::fs::read_to_string(path)
+                .map_err(|e| format!("failed to read genesis file `{}`: {}", path.display(), e))?;
+        let genesis: GenesisJson = serde_json::from_str(&contents)
+                .map_err(|e| format!("failed to parse genesis file `{}`: {}", path.display(), e))?;
+
+        let mut accounts = BTreeMap::new();
+        for (address, entry) in genesis.alloc {
+                let address = H160::from_str(address.strip_prefix("0x").unwrap_or(&address))
+                        .map_err(|e| format!("invalid address `{}`: {}", address, e))?;
+                let balance = parse_u256(&entry.balance)?;
+                let nonce = entry.nonce.as_deref().map(parse_u256).transpose()?.unwrap_or_default();
+                let code = entry
+                        .code
+                        .as_deref()
+                        .map(|c| hex::decode(c.strip_prefix("0x").unwrap_or(c)))
+                        .transpose()
+                        .map_err(|e| format!("invalid code for `{:?}`: {}", address, e))?
+                        .unwrap_or_default();
+                let mut storage = BTreeMap::new();
+                for (key, value) in entry.storage.unwrap_or_default() {
+                        storage.insert(parse_h256(&key)?, parse_h256(&value)?);
+                }
+
+                accounts.insert(address, evm::GenesisAccount { nonce, balance, storage, code });
+        }
+
+        Ok(accounts)
+}
+
+/// Token properties (symbol, decimals, SS58 prefix) shown by wallets and block explorers.
+fn moonbeam_properties() -> Properties {
+        let mut properties = Properties::new();
+        properties.insert("tokenSymbol".into(), "GLMR".into());
+        properties.insert("tokenDecimals".into(), 18.into());
+        properties.insert("ss58Format".into(), 1284.into());
+        properties
+}
+
+/// Build a `ChainSpec` from a named genesis preset, threading through the options that
+/// vary between networks (bootnodes, telemetry, properties) instead of duplicating the
+/// `ChainSpec::from_genesis` boilerplate in every constructor.
+fn build_chain_spec(
+        name: &str,
+        id: &str,
+        chain_type: ChainType,
+        genesis: impl Fn() -> GenesisConfig + 'static,
+        bootnodes: Vec<MultiaddrWithPeerId>,
+        telemetry: Option<TelemetryEndpoints>,
+        properties: Option<Properties>,
+) -> ChainSpec {
+        ChainSpec::from_genesis(
+                name,
+                id,
+                chain_type,
+                genesis,
+                bootnodes,
+                telemetry,
+                Some(PROTOCOL_ID),
+                properties,
+                Default::default(),
+        )
+}
+
+/// The "development" genesis preset: a single Alice authority and the standard set of
+/// dev accounts, funded on both the Substrate and EVM side.
+pub fn development_genesis() -> GenesisConfig {
+        testnet_genesis(
+                WASM_BINARY.expect("Development wasm binary not available"),
+                // Initial PoA authorities
+                vec![
+                        authority_keys_from_seed("Alice"),
+                ],
+                // Sudo account
+                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                // Pre-funded accounts
+                vec![
+                        endowed_account_from_seed("Alice"),
+                        endowed_account_from_seed("Bob"),
+                        endowed_account_from_seed("Alice//stash"),
+                        endowed_account_from_seed("Bob//stash"),
+                ],
+                BTreeMap::new(),
+                true,
+        )
+}
+
+/// The "local_testnet" genesis preset: Alice and Bob as authorities, plus the full set
+/// of well-known dev accounts.
+pub fn local_genesis() -> GenesisConfig {
+        testnet_genesis(
+                WASM_BINARY.expect("Development wasm binary not available"),
+                // Initial PoA authorities
+                vec![
+                        authority_keys_from_seed("Alice"),
+                        authority_keys_from_seed("Bob"),
+                ],
+                // Sudo account
+                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                // Pre-funded accounts
+                vec![
+                        endowed_account_from_seed("Alice"),
+                        endowed_account_from_seed("Bob"),
+                        endowed_account_from_seed("Charlie"),
+                        endowed_account_from_seed("Dave"),
+                        endowed_account_from_seed("Eve"),
+                        endowed_account_from_seed("Ferdie"),
+                        endowed_account_from_seed("Alice//stash"),
+                        endowed_account_from_seed("Bob//stash"),
+                        endowed_account_from_seed("Charlie//stash"),
+                        endowed_account_from_seed("Dave//stash"),
+                        endowed_account_from_seed("Eve//stash"),
+                        endowed_account_from_seed("Ferdie//stash"),
+                ],
+                BTreeMap::new(),
+                true,
+        )
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
+        Ok(build_chain_spec(
+                "Development",
+                "dev",
+                ChainType::Development,
+                development_genesis,
+                // Bootnodes
+                vec![],
+                // Telemetry
+                None,
+                // Properties
+                None,
+        ))
+}
+
+/// A development chain spec whose EVM genesis accounts are seeded from the `alloc` map
+/// of a standard Ethereum `genesis.json`, so pre-deployed contracts and funded EVM
+/// accounts don't require recompiling the node.
+pub fn genesis_from_json(path: &Path) -> Result<ChainSpec, String> {
+        let evm_accounts = evm_accounts_from_alloc_file(path)?;
         let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
 
-        Ok(ChainSpec::from_genesis(
-                // Name
+        Ok(build_chain_spec(
                 "Development",
-                // ID
                 "dev",
                 ChainType::Development,
                 move || testnet_genesis(
                         wasm_binary,
-                        // Initial PoA authorities
                         vec![
                                 authority_keys_from_seed("Alice"),
                         ],
-                        // Sudo account
                         get_account_id_from_seed::<sr25519::Public>("Alice"),
-                        // Pre-funded accounts
                         vec![
-                                get_account_id_from_seed::<sr25519::Public>("Alice"),
-                                get_account_id_from_seed::<sr25519::Public>("Bob"),
-                                get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-                                get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+                                endowed_account_from_seed("Alice"),
+                                endowed_account_from_seed("Bob"),
+                                endowed_account_from_seed("Alice//stash"),
+                                endowed_account_from_seed("Bob//stash"),
                         ],
+                        evm_accounts.clone(),
                         true,
                 ),
                 // Bootnodes
                 vec![],
                 // Telemetry
                 None,
-                // Protocol ID
-                None,
                 // Properties
                 None,
-                // Extensions
-                None,
         ))
 }
 
 pub fn local_testnet_config() -> Result<ChainSpec, String> {
-        let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
-
-        Ok(ChainSpec::from_genesis(
-                // Name
+        Ok(build_chain_spec(
                 "Local Testnet",
-                // ID
                 "local_testnet",
                 ChainType::Local,
-                move || testnet_genesis(
-                        wasm_binary,
-                        // Initial PoA authorities
-                        vec![
-                                authority_keys_from_seed("Alice"),
-                                authority_keys_from_seed("Bob"),
-                        ],
-                        // Sudo account
-                        get_account_id_from_seed::<sr25519::Public>("Alice"),
-                        // Pre-funded accounts
-                        vec![
-                                get_account_id_from_seed::<sr25519::Public>("Alice"),
-                                get_account_id_from_seed::<sr25519::Public>("Bob"),
-                                get_account_id_from_seed::<sr25519::Public>("Charlie"),
-                                get_account_id_from_seed::<sr25519::Public>("Dave"),
-                                get_account_id_from_seed::<sr25519::Public>("Eve"),
-                                get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-                                get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-                                get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-                                get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-                                get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-                                get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-                                get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-                        ],
-                        true,
-                ),
+                local_genesis,
                 // Bootnodes
                 vec![],
                 // Telemetry
                 None,
-                // Protocol ID
-                None,
                 // Properties
                 None,
-                // Extensions
+        ))
+}
+
+/// Hard-coded Aura/Grandpa authority keys for the staging network. Unlike the dev/local
+/// specs these are not derived from well-known seeds: they're the real keys controlled by
+/// the staging validators.
+fn staging_authorities() -> Vec<(AuraId, GrandpaId)> {
+        vec![
+                (
+                        // Aura
+                        hex!("bd206c1b6ea74834c8585b171a9cf24350d75d76053f3aeffab98200541c7de9").unchecked_into(),
+                        // Grandpa
+                        hex!("d5dbb52a2bd4b5f8c027c9e7a5c61ed4072d03d141c0285821e4615203a3d786").unchecked_into(),
+                ),
+                (
+                        // Aura
+                        hex!("0b7d566015b839b783b90d7fdc60bc03939d5d98b6f4e62e2ea6502514923c05").unchecked_into(),
+                        // Grandpa
+                        hex!("f8fabcb98a16d5dd68a0e0683b3815e1d03aebcae8f6a06b1b05748df424442c").unchecked_into(),
+                ),
+        ]
+}
+
+/// The hard-coded sudo key for the staging network.
+fn staging_sudo_key() -> AccountId {
+        hex!("6ffd3cda3bb5a4520168bce500f8ba078f1dc54aae9f36d1219a628e68ed420c").unchecked_into()
+}
+
+/// The hard-coded sudo/root-endowed accounts for the staging network, Substrate side
+/// paired with the matching EVM address.
+fn staging_endowed_accounts() -> Vec<(AccountId, H160)> {
+        vec![(
+                staging_sudo_key(),
+                H160::from(hex!("61964cc836b0aeb2a9bac28e1fc752481d50b0f5")),
+        )]
+}
+
+/// The "staging" genesis preset: baked-in authority keys and sudo account for the
+/// staging/live network.
+pub fn staging_genesis() -> GenesisConfig {
+        testnet_genesis(
+                WASM_BINARY.expect("Development wasm binary not available"),
+                staging_authorities(),
+                staging_sudo_key(),
+                staging_endowed_accounts(),
+                BTreeMap::new(),
+                true,
+        )
+}
+
+/// A staging/live network spec with baked-in authority keys, telemetry, and chain
+/// properties, following the approach used by other Substrate-based staging specs.
+pub fn staging_testnet_config(bootnodes: Vec<MultiaddrWithPeerId>) -> Result<ChainSpec, String> {
+        Ok(build_chain_spec(
+                "Moonbeam Staging Testnet",
+                "moonbeam_staging_testnet",
+                ChainType::Live,
+                staging_genesis,
+                bootnodes,
+                Some(
+                        TelemetryEndpoints::new(vec![(STAGING_TELEMETRY_URL.to_string(), 0)])
+                                .expect("Staging telemetry url is valid; qed"),
+                ),
+                Some(moonbeam_properties()),
+        ))
+}
+
+/// Read an environment variable and parse it, falling back to `default` if it is unset
+/// or fails to parse.
+fn env_var_or<T: FromStr>(key: &str, default: T) -> T {
+        std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Default number of authorities/prefunded accounts for the benchmark preset when `V`/`A`
+/// aren't set in the environment.
+const DEFAULT_BENCHMARK_VALIDATORS: u32 = 4;
+const DEFAULT_BENCHMARK_ACCOUNTS: u32 = 1000;
+
+/// The "benchmark" genesis preset: `V` authorities and `A` prefunded accounts (env vars,
+/// defaulting to [`DEFAULT_BENCHMARK_VALIDATORS`]/[`DEFAULT_BENCHMARK_ACCOUNTS`]),
+/// generated deterministically from the dev seed strings `validator//<i>` and `account//<i>`.
+/// Used for load-testing block production and storage scaling.
+pub fn benchmark_genesis() -> GenesisConfig {
+        let validators = env_var_or("V", DEFAULT_BENCHMARK_VALIDATORS);
+        let accounts = env_var_or("A", DEFAULT_BENCHMARK_ACCOUNTS);
+
+        let initial_authorities: Vec<_> = (0..validators)
+                .map(|i| authority_keys_from_seed(&format!("validator//{}", i)))
+                .collect();
+
+        let endowed_accounts: Vec<_> = (0..accounts)
+                .map(|i| endowed_account_from_seed(&format!("account//{}", i)))
+                .collect();
+
+        testnet_genesis(
+                WASM_BINARY.expect("Development wasm binary not available"),
+                initial_authorities,
+                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                endowed_accounts,
+                BTreeMap::new(),
+                true,
+        )
+}
+
+/// An env-driven chain spec for load-testing block production and storage scaling. See
+/// [`benchmark_genesis`] for how the authority/account counts are controlled.
+pub fn benchmark_config() -> Result<ChainSpec, String> {
+        Ok(build_chain_spec(
+                "Benchmark",
+                "benchmark",
+                ChainType::Development,
+                benchmark_genesis,
+                // Bootnodes
+                vec![],
+                // Telemetry
+                None,
+                // Properties
                 None,
         ))
 }
 
+/// Look up a named genesis preset, e.g. for `build-spec --chain <name>` or external
+/// chain-spec generators that want a [`GenesisConfig`] without going through
+/// `ChainSpec::from_genesis`.
+pub fn genesis_preset(name: &str) -> Option<GenesisConfig> {
+        match name {
+                "development" | "dev" => Some(development_genesis()),
+                "local_testnet" => Some(local_genesis()),
+                "staging" => Some(staging_genesis()),
+                "benchmark" => Some(benchmark_genesis()),
+                _ => None,
+        }
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
         wasm_binary: &[u8],
         initial_authorities: Vec<(AuraId, GrandpaId)>,
         root_key: AccountId,
-        endowed_accounts: Vec<AccountId>,
+        endowed_accounts: Vec<(AccountId, H160)>,
+        extra_evm_accounts: BTreeMap<H160, evm::GenesisAccount>,
         _enable_println: bool,
 ) -> GenesisConfig {
         let alice_evm_account_id = H160::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap();
@@ -163,6 +458,20 @@ fn testnet_genesis(
                         code: vec![],
                 },
         );
+        // Fund the EVM-side address matching every endowed Substrate dev account, so the
+        // same seed is usable from both Substrate tooling and MetaMask/web3.
+        for (_, evm_account_id) in endowed_accounts.iter() {
+                evm_accounts.insert(
+                        *evm_account_id,
+                        evm::GenesisAccount {
+                                nonce: 0.into(),
+                                balance: U256::from(1u128 << 60),
+                                storage: BTreeMap::new(),
+                                code: vec![],
+                        },
+                );
+        }
+        evm_accounts.extend(extra_evm_accounts);
         GenesisConfig {
                 system: Some(SystemConfig {
                         // Add Wasm runtime to storage.
@@ -171,7 +480,7 @@ fn testnet_genesis(
                 }),
                 balances: Some(BalancesConfig {
                         // Configure endowed accounts with initial balance of 1 << 60.
-                        balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+                        balances: endowed_accounts.iter().cloned().map(|(k, _)|(k, 1 << 60)).collect(),
                 }),
                 aura: Some(AuraConfig {
                         authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -192,3 +501,55 @@ fn testnet_genesis(
                 staking: None,
         }
 }
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        /// A real geth `genesis.json` has sibling top-level keys (`config`, `difficulty`,
+        /// `gasLimit`, ...) alongside `alloc`; make sure we read the `alloc` map out of
+        /// that shape instead of expecting the whole file to be the alloc map, and that
+        /// both hex and decimal balances are accepted.
+        #[test]
+        fn evm_accounts_from_alloc_file_reads_alloc_out_of_a_full_genesis_json() {
+                let genesis_json = r#"{
+                        "config": { "chainId": 1281 },
+                        "difficulty": "0x20000",
+                        "gasLimit": "0x2fefd8",
+                        "alloc": {
+                                "0x1111111111111111111111111111111111111111": {
+                                        "balance": "0xde0b6b3a7640000"
+                                },
+                                "2222222222222222222222222222222222222222": {
+                                        "balance": "1000000000000000000"
+                                }
+                        }
+                }"#;
+
+                let path = std::env::temp_dir().join("moonbeam-chain-spec-test-genesis.json");
+                std::fs::write(&path, genesis_json).expect("can write temp genesis file");
+                let accounts = evm_accounts_from_alloc_file(&path).expect("valid genesis.json parses");
+                std::fs::remove_file(&path).expect("can remove temp genesis file");
+
+                assert_eq!(accounts.len(), 2);
+                let one_ether = U256::from(10u128).pow(U256::from(18));
+                assert_eq!(
+                        accounts[&H160::from(hex!("1111111111111111111111111111111111111111"))].balance,
+                        one_ether,
+                );
+                assert_eq!(
+                        accounts[&H160::from(hex!("2222222222222222222222222222222222222222"))].balance,
+                        one_ether,
+                );
+        }
+
+        /// Pin `evm_account_from_seed` against the well-known "Alice" dev address used
+        /// across Frontier/Moonbeam tooling, so a regression in the keccak-slice or
+        /// `parse_compressed` handling is caught instead of silently producing a
+        /// different (but still 20-byte-shaped) address.
+        #[test]
+        fn evm_account_from_seed_matches_known_dev_address() {
+                let alice = H160::from(hex!("f24ff3a9cf04c71dbc94d0b566f7a27b94566cac"));
+                assert_eq!(evm_account_from_seed("Alice"), alice);
+        }
+}